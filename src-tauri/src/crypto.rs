@@ -0,0 +1,272 @@
+use std::fmt;
+use std::sync::Mutex;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use rand::{rngs::OsRng, RngCore};
+
+use crate::Transaction;
+
+const HEADER_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug)]
+pub enum CryptoError {
+    NotUnlocked,
+    WrongPassphraseOrCorrupted,
+    Serialization(serde_json::Error),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CryptoError::NotUnlocked => write!(f, "ledger is locked; unlock it with a passphrase first"),
+            CryptoError::WrongPassphraseOrCorrupted => {
+                write!(f, "wrong passphrase or corrupted file")
+            }
+            CryptoError::Serialization(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// Holds the key derived from the user's passphrase in memory only; it is
+/// never written to disk. Guarded by a `tauri::State` so the passphrase has
+/// to be supplied again on every app launch.
+#[derive(Default)]
+pub struct CryptoState {
+    inner: Mutex<Option<Unlocked>>,
+}
+
+struct Unlocked {
+    key: [u8; 32],
+    salt: [u8; SALT_LEN],
+}
+
+impl CryptoState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.inner.lock().unwrap().is_some()
+    }
+
+    /// Derives a fresh key from `passphrase` behind a new random salt and
+    /// keeps it in memory. Subsequent saves are encrypted with it.
+    pub fn set_passphrase(&self, passphrase: &str) {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = derive_key(passphrase, &salt);
+        *self.inner.lock().unwrap() = Some(Unlocked { key, salt });
+    }
+
+    /// Re-derives the key from `passphrase` using the salt recorded in an
+    /// existing encrypted file and verifies it against that file's auth tag
+    /// before accepting it, so a wrong passphrase never silently "unlocks".
+    pub fn unlock(&self, passphrase: &str, encrypted_file: &[u8]) -> Result<(), CryptoError> {
+        let header = Header::parse(encrypted_file).ok_or(CryptoError::WrongPassphraseOrCorrupted)?;
+        let key = derive_key(passphrase, &header.salt);
+        decrypt_with_key(&key, &header)?;
+
+        *self.inner.lock().unwrap() = Some(Unlocked {
+            key,
+            salt: header.salt,
+        });
+        Ok(())
+    }
+
+    /// Serializes `transactions` and, if a passphrase is set, encrypts the
+    /// result behind a header of `[version | salt | nonce]` followed by the
+    /// ciphertext and auth tag. Falls back to plain JSON only when `existing`
+    /// (the bytes currently on disk, if any) isn't itself encrypted — since
+    /// the ledger starts locked on every app launch, this stops a save that
+    /// races ahead of `unlock` from silently overwriting an encrypted file
+    /// with plaintext.
+    pub fn encode(&self, transactions: &[Transaction], existing: Option<&[u8]>) -> Result<Vec<u8>, CryptoError> {
+        let guard = self.inner.lock().unwrap();
+
+        if guard.is_none() && existing.is_some_and(is_encrypted) {
+            return Err(CryptoError::NotUnlocked);
+        }
+
+        let json = serde_json::to_vec_pretty(transactions).map_err(CryptoError::Serialization)?;
+
+        let Some(unlocked) = guard.as_ref() else {
+            return Ok(json);
+        };
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&unlocked.key));
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce_bytes), json.as_ref())
+            .map_err(|_| CryptoError::WrongPassphraseOrCorrupted)?;
+
+        let mut out = Vec::with_capacity(1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+        out.push(HEADER_VERSION);
+        out.extend_from_slice(&unlocked.salt);
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    /// Decrypts bytes produced by [`Self::encode`]. Requires the ledger to
+    /// already be unlocked; a failing GCM auth tag is surfaced as
+    /// [`CryptoError::WrongPassphraseOrCorrupted`] rather than a parse error.
+    pub fn decode(&self, bytes: &[u8]) -> Result<Vec<Transaction>, CryptoError> {
+        let guard = self.inner.lock().unwrap();
+        let Some(unlocked) = guard.as_ref() else {
+            return Err(CryptoError::NotUnlocked);
+        };
+
+        let header = Header::parse(bytes).ok_or(CryptoError::WrongPassphraseOrCorrupted)?;
+        let json = decrypt_with_key(&unlocked.key, &header)?;
+        serde_json::from_slice(&json).map_err(CryptoError::Serialization)
+    }
+
+    /// Decodes `bytes` the same way [`crate::load_transactions`] does:
+    /// decrypt when unlocked, parse as plain JSON when the bytes genuinely
+    /// aren't encrypted, or refuse with [`CryptoError::NotUnlocked`] when
+    /// they are encrypted but we don't have the key yet — e.g. a fresh app
+    /// launch that hasn't called `unlock` yet. Without that check this would
+    /// otherwise hand ciphertext to `serde_json` and surface a confusing
+    /// "expected value at line 1 column 1" instead of a decrypt error.
+    pub fn decode_or_plain(&self, bytes: &[u8]) -> Result<Vec<Transaction>, CryptoError> {
+        if !is_encrypted(bytes) {
+            serde_json::from_slice(bytes).map_err(CryptoError::Serialization)
+        } else if self.is_unlocked() {
+            self.decode(bytes)
+        } else {
+            Err(CryptoError::NotUnlocked)
+        }
+    }
+}
+
+/// Whether `bytes` already carries [`CryptoState::encode`]'s header, i.e.
+/// the file is encrypted rather than plain JSON.
+fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.first() == Some(&HEADER_VERSION)
+}
+
+struct Header<'a> {
+    salt: [u8; SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+    ciphertext: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let rest = bytes.strip_prefix(&[HEADER_VERSION])?;
+        if rest.len() < SALT_LEN + NONCE_LEN {
+            return None;
+        }
+        let (salt, rest) = rest.split_at(SALT_LEN);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        Some(Header {
+            salt: salt.try_into().ok()?,
+            nonce: nonce.try_into().ok()?,
+            ciphertext,
+        })
+    }
+}
+
+fn decrypt_with_key(key: &[u8; 32], header: &Header) -> Result<Vec<u8>, CryptoError> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(&header.nonce), header.ciphertext)
+        .map_err(|_| CryptoError::WrongPassphraseOrCorrupted)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8; SALT_LEN]) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .expect("argon2id key derivation with a fixed-size output cannot fail");
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_transactions() -> Vec<Transaction> {
+        vec![Transaction {
+            id: "1".to_string(),
+            description: "Coffee".to_string(),
+            amount: -4.5,
+            transaction_type: "expense".to_string(),
+            category: "Food".to_string(),
+            account: "Checking".to_string(),
+            month: "2024-03".to_string(),
+            date: "2024-03-15".to_string(),
+        }]
+    }
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips() {
+        let crypto = CryptoState::new();
+        crypto.set_passphrase("correct horse battery staple");
+
+        let transactions = sample_transactions();
+        let encoded = crypto.encode(&transactions, None).unwrap();
+        assert!(is_encrypted(&encoded));
+
+        let decoded = crypto.decode(&encoded).unwrap();
+        assert_eq!(decoded.len(), transactions.len());
+        assert_eq!(decoded[0].description, transactions[0].description);
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_to_unlock() {
+        let crypto = CryptoState::new();
+        crypto.set_passphrase("correct horse battery staple");
+        let encoded = crypto.encode(&sample_transactions(), None).unwrap();
+
+        let other = CryptoState::new();
+        let err = other.unlock("wrong passphrase", &encoded).unwrap_err();
+        assert!(matches!(err, CryptoError::WrongPassphraseOrCorrupted));
+    }
+
+    #[test]
+    fn encode_refuses_to_downgrade_encrypted_file_while_locked() {
+        let crypto = CryptoState::new();
+        crypto.set_passphrase("correct horse battery staple");
+        let encrypted = crypto.encode(&sample_transactions(), None).unwrap();
+
+        let locked = CryptoState::new();
+        let err = locked
+            .encode(&sample_transactions(), Some(&encrypted))
+            .unwrap_err();
+        assert!(matches!(err, CryptoError::NotUnlocked));
+    }
+
+    #[test]
+    fn encode_allows_plaintext_when_nothing_encrypted_exists() {
+        let locked = CryptoState::new();
+        let encoded = locked.encode(&sample_transactions(), None).unwrap();
+        assert!(!is_encrypted(&encoded));
+    }
+
+    #[test]
+    fn decode_or_plain_refuses_encrypted_bytes_while_locked() {
+        let crypto = CryptoState::new();
+        crypto.set_passphrase("correct horse battery staple");
+        let encrypted = crypto.encode(&sample_transactions(), None).unwrap();
+
+        let locked = CryptoState::new();
+        let err = locked.decode_or_plain(&encrypted).unwrap_err();
+        assert!(matches!(err, CryptoError::NotUnlocked));
+    }
+
+    #[test]
+    fn decode_or_plain_parses_plain_json_while_locked() {
+        let locked = CryptoState::new();
+        let json = serde_json::to_vec(&sample_transactions()).unwrap();
+        let decoded = locked.decode_or_plain(&json).unwrap();
+        assert_eq!(decoded.len(), sample_transactions().len());
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Structured error surfaced to the frontend so the UI can distinguish
+/// failure kinds (e.g. "file missing" vs. "permission denied" vs. "corrupt
+/// JSON") instead of pattern-matching on a free-form string.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum LedgerError {
+    DataDirUnavailable(String),
+    Io(String),
+    Serialization(String),
+    Decrypt(String),
+    NotFound(String),
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            LedgerError::DataDirUnavailable(m)
+            | LedgerError::Io(m)
+            | LedgerError::Serialization(m)
+            | LedgerError::Decrypt(m)
+            | LedgerError::NotFound(m) => m,
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl std::error::Error for LedgerError {}
+
+/// Marker cause for [`anyhow::Context`] so [`LedgerError::from`] can tell a
+/// missing app data directory apart from an ordinary I/O failure.
+#[derive(Debug)]
+pub struct DataDirUnavailable;
+
+impl fmt::Display for DataDirUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "could not resolve the app data directory")
+    }
+}
+
+impl std::error::Error for DataDirUnavailable {}
+
+impl From<anyhow::Error> for LedgerError {
+    /// Classifies the root cause of `err` into a variant the frontend can
+    /// branch on, while keeping the full `anyhow::Context` chain in the
+    /// message so the "where in the pipeline" detail isn't lost.
+    fn from(err: anyhow::Error) -> Self {
+        let message = format!("{:#}", err);
+
+        if err.downcast_ref::<DataDirUnavailable>().is_some() {
+            return LedgerError::DataDirUnavailable(message);
+        }
+        if let Some(crypto_err) = err.downcast_ref::<crate::crypto::CryptoError>() {
+            return match crypto_err {
+                crate::crypto::CryptoError::Serialization(_) => LedgerError::Serialization(message),
+                _ => LedgerError::Decrypt(message),
+            };
+        }
+        if let Some(io_err) = err.downcast_ref::<std::io::Error>() {
+            return if io_err.kind() == std::io::ErrorKind::NotFound {
+                LedgerError::NotFound(message)
+            } else {
+                LedgerError::Io(message)
+            };
+        }
+        if err.downcast_ref::<serde_json::Error>().is_some() {
+            return LedgerError::Serialization(message);
+        }
+        LedgerError::Io(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_data_dir_unavailable() {
+        let err = anyhow::Error::new(DataDirUnavailable).context("resolving the ledger's data file path");
+        assert!(matches!(LedgerError::from(err), LedgerError::DataDirUnavailable(_)));
+    }
+
+    #[test]
+    fn classifies_io_not_found_as_not_found() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = anyhow::Error::new(io_err).context("reading transactions.json");
+        assert!(matches!(LedgerError::from(err), LedgerError::NotFound(_)));
+    }
+
+    #[test]
+    fn classifies_other_io_errors_as_io() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let err = anyhow::Error::new(io_err).context("reading transactions.json");
+        assert!(matches!(LedgerError::from(err), LedgerError::Io(_)));
+    }
+
+    #[test]
+    fn classifies_crypto_error_as_decrypt() {
+        let err = anyhow::Error::new(crate::crypto::CryptoError::WrongPassphraseOrCorrupted)
+            .context("decoding transactions");
+        assert!(matches!(LedgerError::from(err), LedgerError::Decrypt(_)));
+    }
+
+    #[test]
+    fn classifies_serde_json_error_as_serialization() {
+        let json_err = serde_json::from_str::<serde_json::Value>("not json").unwrap_err();
+        let err = anyhow::Error::new(json_err).context("decoding transactions");
+        assert!(matches!(LedgerError::from(err), LedgerError::Serialization(_)));
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::Transaction;
+
+/// Date formats a bank export might use, tried in order until one parses.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%m/%d/%Y", "%d/%m/%Y", "%Y%m%d"];
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Ofx,
+    Qif,
+}
+
+/// Maps the app's logical fields to the column headers of a user's CSV
+/// export, since banks don't agree on header names.
+#[derive(Debug, Deserialize)]
+pub struct ColumnMapping {
+    pub date: String,
+    pub description: String,
+    pub amount: String,
+    pub account: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+struct RawRecord {
+    date: String,
+    description: String,
+    amount: f64,
+    account: String,
+}
+
+/// Parses a bank export at `path` into `Transaction`s and returns only the
+/// ones not already present in `existing`, deduplicated by
+/// (date, amount, description) so re-importing an overlapping statement is a
+/// no-op.
+pub fn import_transactions(
+    path: &Path,
+    format: ImportFormat,
+    mapping: Option<ColumnMapping>,
+    default_account: Option<&str>,
+    existing: &[Transaction]
+) -> Result<(Vec<Transaction>, ImportSummary), String> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Failed to read import file: {}", e))?;
+
+    let (raw_records, mut skipped) = match format {
+        ImportFormat::Csv => {
+            let mapping = mapping.ok_or("CSV import requires a column mapping")?;
+            parse_csv(&contents, &mapping)?
+        }
+        ImportFormat::Ofx => (parse_ofx(&contents)?, 0),
+        ImportFormat::Qif => (parse_qif(&contents)?, 0),
+    };
+
+    let mut seen: HashSet<(String, String, String)> = existing.iter().map(dedup_key).collect();
+
+    let mut imported = Vec::new();
+
+    for record in raw_records {
+        let Some(date) = normalize_date(&record.date) else {
+            skipped += 1;
+            continue;
+        };
+        let month = date[..7].to_string();
+
+        let account = if record.account.is_empty() {
+            default_account.unwrap_or_default().to_string()
+        } else {
+            record.account
+        };
+
+        let transaction = Transaction {
+            id: Uuid::new_v4().to_string(),
+            description: record.description,
+            amount: record.amount,
+            transaction_type: if record.amount >= 0.0 { "income".to_string() } else { "expense".to_string() },
+            category: String::new(),
+            account,
+            month,
+            date,
+        };
+
+        let key = dedup_key(&transaction);
+        if !seen.insert(key) {
+            skipped += 1;
+            continue;
+        }
+
+        imported.push(transaction);
+    }
+
+    let summary = ImportSummary {
+        imported: imported.len(),
+        skipped,
+    };
+    Ok((imported, summary))
+}
+
+fn dedup_key(transaction: &Transaction) -> (String, String, String) {
+    (
+        transaction.date.clone(),
+        format!("{:.2}", transaction.amount),
+        transaction.description.clone(),
+    )
+}
+
+fn normalize_date(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    // OFX timestamps carry a time (and sometimes a timezone) suffix after the
+    // 8-digit date, e.g. "20240315120000[-5:EST]".
+    let date_only = if raw.len() > 8 && raw[..8].chars().all(|c| c.is_ascii_digit()) {
+        &raw[..8]
+    } else {
+        raw
+    };
+
+    for format in DATE_FORMATS {
+        if let Ok(date) = NaiveDate::parse_from_str(date_only, format) {
+            return Some(date.format("%Y-%m-%d").to_string());
+        }
+    }
+    None
+}
+
+/// Parses every row of a CSV export. A row whose amount column doesn't parse
+/// as a number is skipped (and counted) rather than failing the whole
+/// import, same as an unparseable date is handled by the caller.
+fn parse_csv(contents: &str, mapping: &ColumnMapping) -> Result<(Vec<RawRecord>, usize), String> {
+    let mut reader = csv::Reader::from_reader(contents.as_bytes());
+    let headers = reader.headers().map_err(|e| format!("Failed to read CSV headers: {}", e))?.clone();
+
+    let column_index = |name: &str| -> Result<usize, String> {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| format!("CSV is missing mapped column \"{}\"", name))
+    };
+    let date_col = column_index(&mapping.date)?;
+    let description_col = column_index(&mapping.description)?;
+    let amount_col = column_index(&mapping.amount)?;
+    let account_col = column_index(&mapping.account)?;
+
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for result in reader.records() {
+        let row = result.map_err(|e| format!("Failed to read CSV row: {}", e))?;
+        let Some(amount) = row
+            .get(amount_col)
+            .and_then(|s| s.trim().replace(',', "").parse::<f64>().ok())
+        else {
+            skipped += 1;
+            continue;
+        };
+
+        records.push(RawRecord {
+            date: row.get(date_col).unwrap_or_default().to_string(),
+            description: row.get(description_col).unwrap_or_default().to_string(),
+            amount,
+            account: row.get(account_col).unwrap_or_default().to_string(),
+        });
+    }
+    Ok((records, skipped))
+}
+
+/// Parses the `STMTTRN` blocks of an OFX export. OFX is SGML-like rather
+/// than strict XML, so this scans line by line for the tags we need instead
+/// of pulling in a full parser. The source account lives in `<BANKACCTFROM>`
+/// (or `<CCACCTFROM>` for a credit card export), ahead of the transaction
+/// list, and applies to every `STMTTRN` that follows it.
+fn parse_ofx(contents: &str) -> Result<Vec<RawRecord>, String> {
+    let mut records = Vec::new();
+    let mut account = String::new();
+    let mut date = String::new();
+    let mut amount: Option<f64> = None;
+    let mut description = String::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(value) = tag_value(line, "ACCTID") {
+            account = value.to_string();
+        } else if let Some(value) = tag_value(line, "DTPOSTED") {
+            date = value.to_string();
+        } else if let Some(value) = tag_value(line, "TRNAMT") {
+            amount = value.parse().ok();
+        } else if let Some(value) = tag_value(line, "NAME").or_else(|| tag_value(line, "MEMO")) {
+            description = value.to_string();
+        } else if line.eq_ignore_ascii_case("</STMTTRN>") {
+            if let Some(amount) = amount.take() {
+                records.push(RawRecord {
+                    date: std::mem::take(&mut date),
+                    description: std::mem::take(&mut description),
+                    amount,
+                    account: account.clone(),
+                });
+            }
+        }
+    }
+    Ok(records)
+}
+
+fn tag_value<'a>(line: &'a str, tag: &str) -> Option<&'a str> {
+    let prefix = format!("<{}>", tag);
+    line.strip_prefix(prefix.as_str())
+        .map(|rest| rest.trim_end_matches(['\r', '\n']))
+}
+
+/// Parses a QIF export's `!Type:Bank` transaction records: `D` date,
+/// `T` amount, `P`/`M` description, each record terminated by a `^` line.
+/// A leading `!Account` block (its `N` line naming the account) applies to
+/// every transaction record until a new `!Account` block replaces it.
+fn parse_qif(contents: &str) -> Result<Vec<RawRecord>, String> {
+    let mut records = Vec::new();
+    let mut account = String::new();
+    let mut in_account_block = false;
+    let mut date = String::new();
+    let mut amount: Option<f64> = None;
+    let mut description = String::new();
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.eq_ignore_ascii_case("!Account") {
+            in_account_block = true;
+        } else if line.starts_with('!') {
+            in_account_block = false;
+        } else if in_account_block {
+            if let Some(rest) = line.strip_prefix('N') {
+                account = rest.to_string();
+            }
+        } else if let Some(rest) = line.strip_prefix('D') {
+            date = rest.to_string();
+        } else if let Some(rest) = line.strip_prefix('T') {
+            amount = rest.replace(',', "").parse().ok();
+        } else if let Some(rest) = line.strip_prefix('P').or_else(|| line.strip_prefix('M')) {
+            description = rest.to_string();
+        } else if line == "^" {
+            if let Some(amount) = amount.take() {
+                records.push(RawRecord {
+                    date: std::mem::take(&mut date),
+                    description: std::mem::take(&mut description),
+                    amount,
+                    account: account.clone(),
+                });
+            }
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> ColumnMapping {
+        ColumnMapping {
+            date: "Date".to_string(),
+            description: "Description".to_string(),
+            amount: "Amount".to_string(),
+            account: "Account".to_string(),
+        }
+    }
+
+    #[test]
+    fn normalize_date_tries_every_known_format() {
+        assert_eq!(normalize_date("2024-03-15"), Some("2024-03-15".to_string()));
+        assert_eq!(normalize_date("03/15/2024"), Some("2024-03-15".to_string()));
+        assert_eq!(normalize_date("15/03/2024"), Some("2024-03-15".to_string()));
+        assert_eq!(normalize_date("20240315"), Some("2024-03-15".to_string()));
+        assert_eq!(normalize_date("20240315120000[-5:EST]"), Some("2024-03-15".to_string()));
+        assert_eq!(normalize_date("not a date"), None);
+    }
+
+    #[test]
+    fn parse_csv_maps_columns_by_header_name() {
+        let contents = "Date,Description,Amount,Account\n2024-03-15,Coffee,-4.50,Checking\n";
+        let (records, skipped) = parse_csv(contents, &mapping()).unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].description, "Coffee");
+        assert_eq!(records[0].amount, -4.50);
+        assert_eq!(records[0].account, "Checking");
+    }
+
+    #[test]
+    fn parse_csv_skips_rows_with_unparseable_amounts_instead_of_aborting() {
+        let contents = "Date,Description,Amount,Account\n\
+                         2024-03-15,Coffee,-4.50,Checking\n\
+                         2024-03-16,Bad Row,not-a-number,Checking\n\
+                         2024-03-17,Lunch,-12.00,Checking\n";
+        let (records, skipped) = parse_csv(contents, &mapping()).unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(records.len(), 2);
+    }
+
+    #[test]
+    fn parse_ofx_extracts_stmttrn_blocks() {
+        let contents = "<STMTTRN>\n<DTPOSTED>20240315120000\n<TRNAMT>-4.50\n<NAME>Coffee\n</STMTTRN>";
+        let records = parse_ofx(contents).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].date, "20240315120000");
+        assert_eq!(records[0].amount, -4.50);
+        assert_eq!(records[0].description, "Coffee");
+    }
+
+    #[test]
+    fn parse_ofx_reads_account_from_bankacctfrom() {
+        let contents = "<BANKACCTFROM>\n<ACCTID>12345\n</BANKACCTFROM>\n\
+                         <STMTTRN>\n<DTPOSTED>20240315120000\n<TRNAMT>-4.50\n<NAME>Coffee\n</STMTTRN>\n\
+                         <STMTTRN>\n<DTPOSTED>20240316120000\n<TRNAMT>-5.00\n<NAME>Lunch\n</STMTTRN>";
+        let records = parse_ofx(contents).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].account, "12345");
+        assert_eq!(records[1].account, "12345");
+    }
+
+    #[test]
+    fn parse_qif_extracts_bank_records() {
+        let contents = "D2024-03-15\nT-4.50\nPCoffee\n^\n";
+        let records = parse_qif(contents).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].date, "2024-03-15");
+        assert_eq!(records[0].amount, -4.50);
+        assert_eq!(records[0].description, "Coffee");
+    }
+
+    #[test]
+    fn parse_qif_reads_account_from_account_header() {
+        let contents = "!Account\nNChecking\nTBank\n^\n!Type:Bank\nD2024-03-15\nT-4.50\nPCoffee\n^\n";
+        let records = parse_qif(contents).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].account, "Checking");
+    }
+
+    #[test]
+    fn dedup_key_matches_on_date_amount_and_description() {
+        let a = Transaction {
+            id: "1".to_string(),
+            description: "Coffee".to_string(),
+            amount: -4.5,
+            transaction_type: "expense".to_string(),
+            category: String::new(),
+            account: "Checking".to_string(),
+            month: "2024-03".to_string(),
+            date: "2024-03-15".to_string(),
+        };
+        let mut b = Transaction { id: "2".to_string(), ..a.clone() };
+        assert_eq!(dedup_key(&a), dedup_key(&b));
+
+        b.amount = -4.51;
+        assert_ne!(dedup_key(&a), dedup_key(&b));
+    }
+}
@@ -3,9 +3,24 @@
     windows_subsystem = "windows"
 )]
 
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+mod crypto;
+mod error;
+mod import;
+mod reports;
+mod snapshots;
+mod store;
+
+use crypto::CryptoState;
+use error::{DataDirUnavailable, LedgerError};
+use import::{ColumnMapping, ImportFormat, ImportSummary};
+use reports::{AccountBalance, CategorySummary, MonthSummary};
+use snapshots::SnapshotInfo;
+use store::LedgerStore;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct Transaction {
@@ -19,53 +34,250 @@ struct Transaction {
     date: String,
 }
 
-fn get_data_file_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let app_data_dir = app_handle.path_resolver()
+fn get_app_data_dir(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+    let app_data_dir = app_handle
+        .path_resolver()
         .app_data_dir()
-        .ok_or("Failed to get app data directory")?;
-    
-    fs::create_dir_all(&app_data_dir)?;
-    Ok(app_data_dir.join("transactions.json"))
+        .ok_or(DataDirUnavailable)?;
+
+    fs::create_dir_all(&app_data_dir)
+        .with_context(|| format!("creating app data directory {}", app_data_dir.display()))?;
+    Ok(app_data_dir)
+}
+
+fn get_data_file_path(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+    Ok(get_app_data_dir(app_handle)?.join("transactions.json"))
+}
+
+fn get_history_dir_path(app_handle: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+    Ok(get_app_data_dir(app_handle)?.join("history"))
+}
+
+/// Reads `path` if it exists, or `None` otherwise. Used to give
+/// `CryptoState::encode` a look at what's currently on disk so it can refuse
+/// to downgrade an encrypted file to plaintext.
+fn read_file_if_exists(path: &Path) -> anyhow::Result<Option<Vec<u8>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(fs::read(path).with_context(|| format!("reading {}", path.display()))?))
 }
 
 #[tauri::command]
 async fn save_transactions(
     app_handle: tauri::AppHandle,
+    store: tauri::State<'_, LedgerStore>,
+    crypto: tauri::State<'_, CryptoState>,
     transactions: Vec<Transaction>
+) -> Result<(), LedgerError> {
+    save_transactions_inner(&app_handle, &store, &crypto, &transactions).map_err(LedgerError::from)
+}
+
+fn save_transactions_inner(
+    app_handle: &tauri::AppHandle,
+    store: &LedgerStore,
+    crypto: &CryptoState,
+    transactions: &[Transaction]
+) -> anyhow::Result<()> {
+    let file_path = get_data_file_path(app_handle).context("resolving the ledger's data file path")?;
+    let history_dir = get_history_dir_path(app_handle).context("resolving the ledger's history directory")?;
+    let existing = read_file_if_exists(&file_path).context("reading the current ledger file")?;
+
+    let bytes = crypto
+        .encode(transactions, existing.as_deref())
+        .context("encrypting transactions before save")?;
+
+    store.schedule_save(file_path, history_dir, bytes);
+
+    Ok(())
+}
+
+/// Lists every snapshot taken in `history/`, newest first.
+#[tauri::command]
+async fn list_snapshots(
+    app_handle: tauri::AppHandle,
+    crypto: tauri::State<'_, CryptoState>
+) -> Result<Vec<SnapshotInfo>, String> {
+    let history_dir = get_history_dir_path(&app_handle).map_err(|e| e.to_string())?;
+
+    Ok(snapshots::list_snapshots(&history_dir, &crypto))
+}
+
+/// Restores the snapshot taken at `timestamp` and makes it the active
+/// ledger.
+#[tauri::command]
+async fn restore_snapshot(
+    app_handle: tauri::AppHandle,
+    store: tauri::State<'_, LedgerStore>,
+    crypto: tauri::State<'_, CryptoState>,
+    timestamp: u64
+) -> Result<Vec<Transaction>, String> {
+    let file_path = get_data_file_path(&app_handle).map_err(|e| e.to_string())?;
+    let history_dir = get_history_dir_path(&app_handle).map_err(|e| e.to_string())?;
+    let existing = read_file_if_exists(&file_path).map_err(|e| e.to_string())?;
+
+    let transactions = snapshots::restore_snapshot(&history_dir, timestamp, &crypto)?;
+
+    let bytes = crypto
+        .encode(&transactions, existing.as_deref())
+        .map_err(|e| format!("Failed to encrypt transactions: {}", e))?;
+    store.schedule_save(file_path, history_dir, bytes);
+
+    Ok(transactions)
+}
+
+/// Sets (or changes) the passphrase the ledger is encrypted with. Subsequent
+/// saves are encrypted; existing plaintext data is re-encrypted on the next
+/// save.
+#[tauri::command]
+async fn set_passphrase(
+    crypto: tauri::State<'_, CryptoState>,
+    passphrase: String
 ) -> Result<(), String> {
-    let file_path = get_data_file_path(&app_handle)
-        .map_err(|e| format!("Failed to get data file path: {}", e))?;
-    
-    let json_data = serde_json::to_string_pretty(&transactions)
-        .map_err(|e| format!("Failed to serialize transactions: {}", e))?;
-    
-    fs::write(file_path, json_data)
-        .map_err(|e| format!("Failed to write transactions file: {}", e))?;
-    
+    crypto.set_passphrase(&passphrase);
     Ok(())
 }
 
+/// Unlocks an already-encrypted ledger by re-deriving the key from
+/// `passphrase` and verifying it against the stored file's auth tag.
+#[tauri::command]
+async fn unlock(
+    app_handle: tauri::AppHandle,
+    crypto: tauri::State<'_, CryptoState>,
+    passphrase: String
+) -> Result<(), String> {
+    let file_path = get_data_file_path(&app_handle).map_err(|e| e.to_string())?;
+
+    let encrypted = fs::read(file_path)
+        .map_err(|e| format!("Failed to read transactions file: {}", e))?;
+
+    crypto
+        .unlock(&passphrase, &encrypted)
+        .map_err(|e| format!("{}", e))
+}
+
+/// Forces any pending debounced autosave out to disk. The frontend should
+/// call this before the app exits so a trailing edit isn't lost.
 #[tauri::command]
-async fn load_transactions(app_handle: tauri::AppHandle) -> Result<Vec<Transaction>, String> {
-    let file_path = get_data_file_path(&app_handle)
-        .map_err(|e| format!("Failed to get data file path: {}", e))?;
-    
+async fn flush(store: tauri::State<'_, LedgerStore>) -> Result<(), String> {
+    store
+        .flush()
+        .map_err(|e| format!("Failed to flush pending transactions: {}", e))
+}
+
+/// Reads and decodes whatever is currently persisted, or an empty ledger if
+/// nothing has been saved yet: the same read as `load_transactions`, with the
+/// error flattened to a string. Used by import and the report commands, which
+/// need the current ledger to build on.
+fn load_existing_transactions(
+    app_handle: &tauri::AppHandle,
+    crypto: &CryptoState
+) -> Result<Vec<Transaction>, String> {
+    load_transactions_inner(app_handle, crypto).map_err(|e| e.to_string())
+}
+
+fn load_transactions_inner(app_handle: &tauri::AppHandle, crypto: &CryptoState) -> anyhow::Result<Vec<Transaction>> {
+    let file_path = get_data_file_path(app_handle).context("resolving the ledger's data file path")?;
+
     if !file_path.exists() {
         return Ok(Vec::new());
     }
-    
-    let json_data = fs::read_to_string(file_path)
-        .map_err(|e| format!("Failed to read transactions file: {}", e))?;
-    
-    let transactions: Vec<Transaction> = serde_json::from_str(&json_data)
-        .map_err(|e| format!("Failed to parse transactions: {}", e))?;
-    
-    Ok(transactions)
+
+    let bytes = fs::read(&file_path).with_context(|| format!("reading {}", file_path.display()))?;
+
+    crypto.decode_or_plain(&bytes).context("decoding transactions")
+}
+
+#[tauri::command]
+async fn load_transactions(
+    app_handle: tauri::AppHandle,
+    crypto: tauri::State<'_, CryptoState>
+) -> Result<Vec<Transaction>, LedgerError> {
+    load_transactions_inner(&app_handle, &crypto).map_err(LedgerError::from)
+}
+
+/// Imports a bank export at `path` and merges the new transactions into the
+/// active ledger, skipping any that duplicate an existing (date, amount,
+/// description). `default_account` is used for any record that doesn't
+/// carry its own account (e.g. a QIF file with no `!Account` header, or a
+/// CSV row whose mapped account column is empty).
+#[tauri::command]
+async fn import_transactions(
+    app_handle: tauri::AppHandle,
+    store: tauri::State<'_, LedgerStore>,
+    crypto: tauri::State<'_, CryptoState>,
+    path: String,
+    format: ImportFormat,
+    mapping: Option<ColumnMapping>,
+    default_account: Option<String>
+) -> Result<ImportSummary, String> {
+    let mut transactions = load_existing_transactions(&app_handle, &crypto)?;
+
+    let (new_transactions, summary) =
+        import::import_transactions(Path::new(&path), format, mapping, default_account.as_deref(), &transactions)?;
+    transactions.extend(new_transactions);
+
+    let file_path = get_data_file_path(&app_handle).map_err(|e| e.to_string())?;
+    let history_dir = get_history_dir_path(&app_handle).map_err(|e| e.to_string())?;
+    let existing = read_file_if_exists(&file_path).map_err(|e| e.to_string())?;
+    let bytes = crypto
+        .encode(&transactions, existing.as_deref())
+        .map_err(|e| format!("Failed to encrypt transactions: {}", e))?;
+    store.schedule_save(file_path, history_dir, bytes);
+
+    Ok(summary)
+}
+
+/// Returns income/expense/net totals per month, without shipping every
+/// transaction to the webview.
+#[tauri::command]
+async fn summary_by_month(
+    app_handle: tauri::AppHandle,
+    crypto: tauri::State<'_, CryptoState>
+) -> Result<Vec<MonthSummary>, String> {
+    let transactions = load_existing_transactions(&app_handle, &crypto)?;
+    Ok(reports::summary_by_month(&transactions))
+}
+
+/// Returns income/expense/net totals per category, optionally restricted to
+/// a single `month`.
+#[tauri::command]
+async fn summary_by_category(
+    app_handle: tauri::AppHandle,
+    crypto: tauri::State<'_, CryptoState>,
+    month: Option<String>
+) -> Result<Vec<CategorySummary>, String> {
+    let transactions = load_existing_transactions(&app_handle, &crypto)?;
+    Ok(reports::summary_by_category(&transactions, month.as_deref()))
+}
+
+/// Returns income/expense/net totals per account.
+#[tauri::command]
+async fn account_balances(
+    app_handle: tauri::AppHandle,
+    crypto: tauri::State<'_, CryptoState>
+) -> Result<Vec<AccountBalance>, String> {
+    let transactions = load_existing_transactions(&app_handle, &crypto)?;
+    Ok(reports::account_balances(&transactions))
 }
 
 fn main() {
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![save_transactions, load_transactions])
+        .manage(LedgerStore::new())
+        .manage(CryptoState::new())
+        .invoke_handler(tauri::generate_handler![
+            save_transactions,
+            load_transactions,
+            flush,
+            set_passphrase,
+            unlock,
+            list_snapshots,
+            restore_snapshot,
+            import_transactions,
+            summary_by_month,
+            summary_by_category,
+            account_balances
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
\ No newline at end of file
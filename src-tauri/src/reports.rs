@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::Transaction;
+
+#[derive(Debug, Serialize, Default, Clone)]
+pub struct Totals {
+    pub income: f64,
+    pub expense: f64,
+    pub net: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonthSummary {
+    pub month: String,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategorySummary {
+    pub category: String,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountBalance {
+    pub account: String,
+    pub totals: Totals,
+}
+
+/// Pre-aggregates `transactions` by month so the frontend doesn't have to
+/// load every row just to draw a monthly chart.
+pub fn summary_by_month(transactions: &[Transaction]) -> Vec<MonthSummary> {
+    let mut by_month: BTreeMap<String, Totals> = BTreeMap::new();
+    for transaction in transactions {
+        accumulate(by_month.entry(transaction.month.clone()).or_default(), transaction);
+    }
+
+    by_month
+        .into_iter()
+        .map(|(month, totals)| MonthSummary { month, totals })
+        .collect()
+}
+
+/// Pre-aggregates `transactions` by category, optionally restricted to a
+/// single `month`.
+pub fn summary_by_category(transactions: &[Transaction], month: Option<&str>) -> Vec<CategorySummary> {
+    let mut by_category: BTreeMap<String, Totals> = BTreeMap::new();
+    for transaction in transactions {
+        if month.is_some_and(|month| transaction.month != month) {
+            continue;
+        }
+        accumulate(by_category.entry(transaction.category.clone()).or_default(), transaction);
+    }
+
+    by_category
+        .into_iter()
+        .map(|(category, totals)| CategorySummary { category, totals })
+        .collect()
+}
+
+/// Pre-aggregates `transactions` by account.
+pub fn account_balances(transactions: &[Transaction]) -> Vec<AccountBalance> {
+    let mut by_account: BTreeMap<String, Totals> = BTreeMap::new();
+    for transaction in transactions {
+        accumulate(by_account.entry(transaction.account.clone()).or_default(), transaction);
+    }
+
+    by_account
+        .into_iter()
+        .map(|(account, totals)| AccountBalance { account, totals })
+        .collect()
+}
+
+fn accumulate(totals: &mut Totals, transaction: &Transaction) {
+    if transaction.transaction_type == "income" {
+        totals.income += transaction.amount.abs();
+    } else {
+        totals.expense += transaction.amount.abs();
+    }
+    totals.net = totals.income - totals.expense;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(month: &str, category: &str, account: &str, transaction_type: &str, amount: f64) -> Transaction {
+        Transaction {
+            id: "1".to_string(),
+            description: String::new(),
+            amount,
+            transaction_type: transaction_type.to_string(),
+            category: category.to_string(),
+            account: account.to_string(),
+            month: month.to_string(),
+            date: format!("{}-01", month),
+        }
+    }
+
+    #[test]
+    fn summary_by_month_nets_income_and_expense() {
+        let transactions = vec![
+            transaction("2024-03", "Salary", "Checking", "income", 2000.0),
+            transaction("2024-03", "Food", "Checking", "expense", -50.0),
+            transaction("2024-04", "Food", "Checking", "expense", -20.0),
+        ];
+
+        let summaries = summary_by_month(&transactions);
+        assert_eq!(summaries.len(), 2);
+
+        let march = summaries.iter().find(|s| s.month == "2024-03").unwrap();
+        assert_eq!(march.totals.income, 2000.0);
+        assert_eq!(march.totals.expense, 50.0);
+        assert_eq!(march.totals.net, 1950.0);
+
+        let april = summaries.iter().find(|s| s.month == "2024-04").unwrap();
+        assert_eq!(april.totals.income, 0.0);
+        assert_eq!(april.totals.expense, 20.0);
+        assert_eq!(april.totals.net, -20.0);
+    }
+
+    #[test]
+    fn summary_by_category_filters_by_month() {
+        let transactions = vec![
+            transaction("2024-03", "Food", "Checking", "expense", -50.0),
+            transaction("2024-04", "Food", "Checking", "expense", -20.0),
+        ];
+
+        let all = summary_by_category(&transactions, None);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].totals.expense, 70.0);
+
+        let march_only = summary_by_category(&transactions, Some("2024-03"));
+        assert_eq!(march_only.len(), 1);
+        assert_eq!(march_only[0].totals.expense, 50.0);
+
+        let no_match = summary_by_category(&transactions, Some("2024-05"));
+        assert!(no_match.is_empty());
+    }
+
+    #[test]
+    fn account_balances_groups_by_account() {
+        let transactions = vec![
+            transaction("2024-03", "Salary", "Checking", "income", 2000.0),
+            transaction("2024-03", "Food", "Savings", "expense", -50.0),
+        ];
+
+        let balances = account_balances(&transactions);
+        assert_eq!(balances.len(), 2);
+
+        let checking = balances.iter().find(|b| b.account == "Checking").unwrap();
+        assert_eq!(checking.totals.net, 2000.0);
+
+        let savings = balances.iter().find(|b| b.account == "Savings").unwrap();
+        assert_eq!(savings.totals.net, -50.0);
+    }
+}
@@ -0,0 +1,120 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::crypto::CryptoState;
+use crate::Transaction;
+
+/// How many snapshots to keep in `history/` before pruning the oldest.
+const MAX_SNAPSHOTS: usize = 50;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SnapshotInfo {
+    timestamp: u64,
+    transaction_count: usize,
+}
+
+/// Writes a timestamped copy of `bytes` (the same encoded payload just
+/// persisted as the active ledger) into `history_dir`, then prunes anything
+/// past the last `MAX_SNAPSHOTS`.
+pub fn write_snapshot(history_dir: &Path, bytes: &[u8]) -> io::Result<()> {
+    fs::create_dir_all(history_dir)?;
+
+    let mut timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    // Millisecond timestamps can still collide on back-to-back writes (e.g.
+    // two flushes landing in the same millisecond); bump to the next free
+    // slot so a later snapshot never silently clobbers an earlier one.
+    while history_dir.join(format!("{}.json", timestamp)).exists() {
+        timestamp += 1;
+    }
+    fs::write(history_dir.join(format!("{}.json", timestamp)), bytes)?;
+
+    prune_old_snapshots(history_dir)
+}
+
+fn prune_old_snapshots(history_dir: &Path) -> io::Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(history_dir)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    if entries.len() > MAX_SNAPSHOTS {
+        for entry in &entries[..entries.len() - MAX_SNAPSHOTS] {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Reads every snapshot in `history_dir` in parallel and returns their
+/// `{timestamp, transaction_count}`, newest first. A snapshot that fails to
+/// parse (e.g. still encrypted and locked) is logged and omitted rather than
+/// failing the whole listing.
+pub fn list_snapshots(history_dir: &Path, crypto: &CryptoState) -> Vec<SnapshotInfo> {
+    if !history_dir.exists() {
+        return Vec::new();
+    }
+
+    let entries = match fs::read_dir(history_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("failed to read history directory: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+
+    let (tx, rx) = mpsc::channel();
+    paths.par_iter().for_each_with(tx, |tx, path| match parse_snapshot(path, crypto) {
+        Ok(info) => {
+            let _ = tx.send(info);
+        }
+        Err(e) => eprintln!("skipping unparseable snapshot {}: {}", path.display(), e),
+    });
+
+    let mut snapshots: Vec<SnapshotInfo> = rx.into_iter().collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    snapshots
+}
+
+fn parse_snapshot(path: &Path, crypto: &CryptoState) -> Result<SnapshotInfo, String> {
+    let timestamp = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .and_then(|s| s.parse::<u64>().ok())
+        .ok_or("filename is not a unix timestamp")?;
+
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let transactions = crypto.decode_or_plain(&bytes).map_err(|e| e.to_string())?;
+
+    Ok(SnapshotInfo {
+        timestamp,
+        transaction_count: transactions.len(),
+    })
+}
+
+/// Loads the snapshot taken at `timestamp` so it can be restored as the
+/// active ledger.
+pub fn restore_snapshot(
+    history_dir: &Path,
+    timestamp: u64,
+    crypto: &CryptoState
+) -> Result<Vec<Transaction>, String> {
+    let path = history_dir.join(format!("{}.json", timestamp));
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read snapshot: {}", e))?;
+    crypto
+        .decode_or_plain(&bytes)
+        .map_err(|e| format!("Failed to parse snapshot: {}", e))
+}
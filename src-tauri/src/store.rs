@@ -0,0 +1,135 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::snapshots;
+
+const AUTOSAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct Pending {
+    generation: u64,
+    data: Option<(PathBuf, PathBuf, Vec<u8>)>,
+}
+
+/// Coalesces rapid `save_transactions` calls into a single debounced disk
+/// flush, and persists that flush by writing a sibling temp file followed by
+/// an atomic rename so a crash mid-write can't truncate `transactions.json`.
+#[derive(Default)]
+pub struct LedgerStore {
+    pending: Arc<Mutex<Pending>>,
+}
+
+impl LedgerStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the already-encoded `bytes` to be written to `path`, with a
+    /// history snapshot taken alongside into `history_dir` once the write
+    /// actually lands. If another call comes in within `AUTOSAVE_DEBOUNCE`,
+    /// it replaces this one and owns the flush. Encoding (plain JSON or
+    /// encryption) is the caller's concern; the store only owns getting
+    /// bytes onto disk safely.
+    pub fn schedule_save(&self, path: PathBuf, history_dir: PathBuf, bytes: Vec<u8>) {
+        let generation = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.generation += 1;
+            pending.data = Some((path, history_dir, bytes));
+            pending.generation
+        };
+
+        let pending = Arc::clone(&self.pending);
+        thread::spawn(move || {
+            thread::sleep(AUTOSAVE_DEBOUNCE);
+            let mut pending = pending.lock().unwrap();
+            if pending.generation != generation {
+                return;
+            }
+            if let Some((path, history_dir, bytes)) = pending.data.take() {
+                persist(&path, &history_dir, &bytes);
+            }
+        });
+    }
+
+    /// Force any pending debounced write out to disk immediately. The
+    /// frontend calls this before exiting so a trailing edit isn't lost.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        if let Some((path, history_dir, bytes)) = pending.data.take() {
+            write_atomic(&path, &bytes)?;
+            if let Err(e) = snapshots::write_snapshot(&history_dir, &bytes) {
+                eprintln!("failed to write snapshot: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn persist(path: &Path, history_dir: &Path, bytes: &[u8]) {
+    if let Err(e) = write_atomic(path, bytes) {
+        eprintln!("autosave flush failed: {}", e);
+        return;
+    }
+    if let Err(e) = snapshots::write_snapshot(history_dir, bytes) {
+        eprintln!("failed to write snapshot: {}", e);
+    }
+}
+
+/// Writes `bytes` to `path` via a sibling `.tmp` file plus `fs::rename`,
+/// which is atomic on the same filesystem.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh scratch directory under the OS temp dir, unique to `name` and
+    /// the current process so parallel test runs don't collide.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("familyledger-store-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn schedule_save_coalesces_rapid_calls_onto_the_latest_payload() {
+        let dir = temp_dir("coalesce");
+        let path = dir.join("transactions.json");
+        let history_dir = dir.join("history");
+
+        let store = LedgerStore::new();
+        store.schedule_save(path.clone(), history_dir.clone(), b"first".to_vec());
+        store.schedule_save(path.clone(), history_dir.clone(), b"second".to_vec());
+
+        thread::sleep(AUTOSAVE_DEBOUNCE + Duration::from_millis(200));
+
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn flush_writes_immediately_without_waiting_for_the_debounce() {
+        let dir = temp_dir("flush");
+        let path = dir.join("transactions.json");
+        let history_dir = dir.join("history");
+
+        let store = LedgerStore::new();
+        store.schedule_save(path.clone(), history_dir.clone(), b"payload".to_vec());
+        store.flush().unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"payload");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}